@@ -0,0 +1,85 @@
+// Copyright 2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `Encodable`/`Decodable` for the big, fixed-width unsigned integers from
+//! the `uint` crate (`U128`, `U256`, `U512`), gated behind the `uint`
+//! feature. These are the types `ethereum-types` builds its `U256` balances
+//! and difficulties on top of, so downstream Ethereum code can encode them
+//! directly instead of going through a manual `Vec<u8>` conversion.
+//!
+//! Only compiled when the `uint` feature is enabled; see this crate's
+//! `Cargo.toml` for the `uint`/`ethereum-types` optional dependency.
+//!
+//! This module is named `bigint`, not `uint`, even though it wraps the
+//! `uint` crate: a local module named `uint` would make every `uint::...`
+//! path in the crate root or a sibling module ambiguous between this module
+//! and the extern-prelude crate of the same name (`E0659`).
+#![cfg(feature = "uint")]
+
+use core::mem;
+
+use uint::{U128, U256, U512};
+
+use crate::error::DecoderError;
+use crate::rlpin::Rlp;
+use crate::stream::RlpStream;
+use crate::traits::{Decodable, Encodable};
+
+macro_rules! impl_uint_rlp {
+	($name: ident, $size: expr) => {
+		impl Encodable for $name {
+			fn rlp_append(&self, s: &mut RlpStream) {
+				let leading_empty_bytes = self.leading_zeros() as usize / 8;
+				let mut buffer = [0u8; $size];
+				self.to_big_endian(&mut buffer);
+				s.encoder().encode_value(&buffer[leading_empty_bytes..]);
+			}
+		}
+
+		impl Decodable for $name {
+			fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+				rlp.decoder().decode_value(|bytes| match bytes.len() {
+					0 => Ok($name::zero()),
+					l if l <= mem::size_of::<$name>() => {
+						if bytes[0] == 0 {
+							return Err(DecoderError::RlpInvalidIndirection);
+						}
+						Ok($name::from_big_endian(bytes))
+					}
+					_ => Err(DecoderError::RlpIsTooBig),
+				})
+			}
+		}
+	};
+}
+
+impl_uint_rlp!(U128, 16);
+impl_uint_rlp!(U256, 32);
+impl_uint_rlp!(U512, 64);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{decode, encode};
+
+	#[test]
+	fn u256_round_trips_and_trims_leading_zeros() {
+		let value = U256::from(0x1234u64);
+		let encoded = encode(&value);
+		// Same trimming behavior as the u64 impl: no leading zero bytes kept.
+		assert_eq!(encoded, vec![0x82, 0x12, 0x34]);
+		assert_eq!(decode::<U256>(&encoded).unwrap(), value);
+	}
+
+	#[test]
+	fn u512_round_trips_max_width() {
+		let value = U512::MAX;
+		let encoded = encode(&value);
+		assert_eq!(decode::<U512>(&encoded).unwrap(), value);
+	}
+}
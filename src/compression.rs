@@ -0,0 +1,187 @@
+// Copyright 2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lossless compression for RLP-encoded payloads.
+//!
+//! Real-world RLP streams (Ethereum state, in particular) are dominated by a
+//! handful of recurring fragments: 32-/33-byte all-zero blobs used as
+//! placeholder hashes or signatures. [`compress`] replaces any occurrence of
+//! those fragments with a two-byte escape sequence, and [`decompress`]
+//! reverses the substitution exactly. Single-byte fragments (`0xc0`, `0x80`,
+//! ...) are deliberately not in the table: substituting a two-byte escape
+//! sequence for a one-byte fragment would grow the output instead of
+//! shrinking it.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::error::DecoderError;
+
+/// Reserved byte that introduces a substitution (or an escaped literal) in
+/// compressed output. Chosen from the high, rarely-produced long-list RLP
+/// prefix range so it almost never collides with real payload bytes.
+const ESCAPE: u8 = 0xfe;
+
+/// Follows [`ESCAPE`] to mean "the next byte is a literal `ESCAPE` byte from
+/// the original data, not a substitution id".
+const LITERAL: u8 = 0x00;
+
+/// A fragment eligible for substitution and the one-byte id that follows
+/// [`ESCAPE`] in its place.
+struct Fragment {
+	id: u8,
+	bytes: &'static [u8],
+}
+
+/// Table of recurring RLP fragments, longest first so [`compress`] always
+/// prefers the most specific match. Only fragments longer than the two-byte
+/// escape sequence that replaces them belong here.
+static FRAGMENTS: &[Fragment] = &[Fragment { id: 3, bytes: &[0u8; 33] }, Fragment { id: 4, bytes: &[0u8; 32] }];
+
+fn fragment_by_id(id: u8) -> Option<&'static Fragment> {
+	FRAGMENTS.iter().find(|f| f.id == id)
+}
+
+/// Replace recurring fragments in `data` with two-byte escape sequences
+/// ([`ESCAPE`] followed by the fragment's id).
+///
+/// Any literal `ESCAPE` byte already present in `data` is escaped so that
+/// [`decompress`] can tell it apart from a substitution.
+pub fn compress(data: &[u8]) -> Vec<u8> {
+	let mut out = Vec::with_capacity(data.len());
+	let mut i = 0;
+	'outer: while i < data.len() {
+		if data[i] == ESCAPE {
+			out.push(ESCAPE);
+			out.push(LITERAL);
+			out.push(ESCAPE);
+			i += 1;
+			continue;
+		}
+		for fragment in FRAGMENTS {
+			if data[i..].starts_with(fragment.bytes) {
+				out.push(ESCAPE);
+				out.push(fragment.id);
+				i += fragment.bytes.len();
+				continue 'outer;
+			}
+		}
+		out.push(data[i]);
+		i += 1;
+	}
+	out
+}
+
+/// Reverse a [`compress`]ed byte stream back to its original bytes.
+///
+/// Returns `Err` if the stream ends in the middle of an escape sequence or
+/// names a sentinel id that isn't in [`FRAGMENTS`], since both indicate
+/// corrupt or ambiguous input.
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, DecoderError> {
+	let mut out = Vec::with_capacity(data.len());
+	let mut i = 0;
+	while i < data.len() {
+		if data[i] == ESCAPE {
+			let marker = *data.get(i + 1).ok_or(DecoderError::RlpIsTooShort)?;
+			if marker == LITERAL {
+				let literal = *data.get(i + 2).ok_or(DecoderError::RlpIsTooShort)?;
+				out.push(literal);
+				i += 3;
+			} else {
+				let fragment = fragment_by_id(marker).ok_or(DecoderError::RlpInvalidIndirection)?;
+				out.extend_from_slice(fragment.bytes);
+				i += 2;
+			}
+		} else {
+			out.push(data[i]);
+			i += 1;
+		}
+	}
+	Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn round_trips_known_fragments() {
+		let mut input = Vec::new();
+		input.extend_from_slice(&[0xc0]);
+		input.extend_from_slice(&[0u8; 32]);
+		input.extend_from_slice(b"hello");
+		input.extend_from_slice(&[0x80]);
+
+		let compressed = compress(&input);
+		assert!(compressed.len() < input.len());
+		assert_eq!(decompress(&compressed).unwrap(), input);
+	}
+
+	#[test]
+	fn round_trips_literal_escape_byte() {
+		let input = vec![1, 2, ESCAPE, 3, ESCAPE, ESCAPE];
+		let compressed = compress(&input);
+		assert_eq!(decompress(&compressed).unwrap(), input);
+	}
+
+	#[test]
+	fn rejects_unknown_sentinel() {
+		let corrupt = [ESCAPE, 0xff];
+		assert!(decompress(&corrupt).is_err());
+	}
+
+	#[test]
+	fn rejects_truncated_escape() {
+		let corrupt = [ESCAPE];
+		assert!(decompress(&corrupt).is_err());
+	}
+
+	#[test]
+	fn round_trips_through_rlp_stream_and_rlp() {
+		use crate::rlpin::Rlp;
+		use crate::stream::RlpStream;
+		use crate::traits::Decodable;
+
+		// A 32-byte all-zero "null hash" is exactly the kind of fragment this
+		// module targets: its plain RLP encoding is 33 bytes (`0xa0` header +
+		// 32 zero bytes), and the 32 zero bytes collapse to a two-byte escape
+		// sequence.
+		let hash = [0u8; 32];
+
+		let mut stream = RlpStream::new();
+		stream.append_compressed(&hash);
+		let wire = stream.out();
+		assert!(wire.len() < 33, "expected the compressed item to beat the 33-byte plain encoding, got {} bytes", wire.len());
+
+		let decompressed = Rlp::new(&wire).decompress().unwrap();
+		let recovered: [u8; 32] = Decodable::decode(&Rlp::new(&decompressed)).unwrap();
+		assert_eq!(recovered, hash);
+	}
+
+	#[test]
+	fn append_compressed_as_list_sibling_does_not_corrupt_the_list() {
+		use crate::rlpin::Rlp;
+		use crate::stream::RlpStream;
+		use crate::traits::Decodable;
+
+		let mut stream = RlpStream::new();
+		stream.begin_list(3);
+		stream.append(&1u32);
+		stream.append_compressed(&[0u8; 32]);
+		stream.append(&3u32);
+		let wire = stream.out();
+
+		let rlp = Rlp::new(&wire);
+		assert_eq!(rlp.item_count().unwrap(), 3);
+		assert_eq!(rlp.val_at::<u32>(0).unwrap(), 1);
+		let decompressed = rlp.at(1).unwrap().decompress().unwrap();
+		let recovered: [u8; 32] = Decodable::decode(&Rlp::new(&decompressed)).unwrap();
+		assert_eq!(recovered, [0u8; 32]);
+		assert_eq!(rlp.val_at::<u32>(2).unwrap(), 3);
+	}
+}
@@ -213,106 +213,242 @@ impl Decodable for String {
 	}
 }
 
+// The bare `iN`/`fN` impls below are non-standard RLP extensions (signed
+// integers via zig-zag, floats via raw IEEE bits) that no other RLP decoder
+// defines, and whose wire encoding is indistinguishable from an unsigned
+// value of the same magnitude. They're opt-in via the `non-standard`
+// feature; reach for `crate::nonstandard::Signed`/`Float` instead when that
+// ambiguity matters, since those are always available. Both delegate to the
+// same `Signed`/`Float` impls so there's one copy of the zig-zag/bit-cast
+// math to keep correct.
+#[cfg(feature = "non-standard")]
 macro_rules! impl_encodable_for_i {
 	($name: ident) => {
 		impl Encodable for $name {
 			fn rlp_append(&self, s: &mut RlpStream) {
-				let i = *self as i128;
-				let zigzag = ((i << 1) ^ (i >> 127)) as u128;
-				let leading_empty_bytes = zigzag.leading_zeros() as usize / 8;
-				let buffer = zigzag.to_be_bytes();
-				s.encoder().encode_value(&buffer[leading_empty_bytes..]);
+				crate::nonstandard::Signed(*self).rlp_append(s)
 			}
 		}
 	};
 }
 
+#[cfg(feature = "non-standard")]
 macro_rules! impl_decodable_for_i {
 	($name: ident) => {
 		impl Decodable for $name {
 			fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-				match u128::decode(rlp) {
-					Ok(res) => {
-						let recover = ((res >> 1) ^ (-((res & 1) as i128)) as u128) as $name;
-						Ok(recover)
-					}
-					Err(err) => Err(err),
-				}
+				crate::nonstandard::Signed::decode(rlp).map(|signed| signed.0)
 			}
 		}
 	};
 }
 
+#[cfg(feature = "non-standard")]
 impl_encodable_for_i!(i8);
+#[cfg(feature = "non-standard")]
 impl_encodable_for_i!(i16);
+#[cfg(feature = "non-standard")]
 impl_encodable_for_i!(i32);
+#[cfg(feature = "non-standard")]
 impl_encodable_for_i!(i64);
+#[cfg(feature = "non-standard")]
 impl_encodable_for_i!(i128);
 
+#[cfg(feature = "non-standard")]
 impl_decodable_for_i!(i8);
+#[cfg(feature = "non-standard")]
 impl_decodable_for_i!(i16);
+#[cfg(feature = "non-standard")]
 impl_decodable_for_i!(i32);
+#[cfg(feature = "non-standard")]
 impl_decodable_for_i!(i64);
+#[cfg(feature = "non-standard")]
 impl_decodable_for_i!(i128);
 
+#[cfg(feature = "non-standard")]
 macro_rules! impl_encodable_for_f {
 	($name: ident, $value : ident) => {
 		impl Encodable for $name {
 			fn rlp_append(&self, s: &mut RlpStream) {
-				let num = $value::from_be_bytes(self.to_bits().to_be_bytes());
-				num.rlp_append(s);
+				crate::nonstandard::Float(*self).rlp_append(s)
 			}
 		}
 	};
 }
 
+#[cfg(feature = "non-standard")]
 macro_rules! impl_decodable_for_f {
 	($name: ident, $value : ident) => {
 		impl Decodable for $name {
 			fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-				match $value::decode(rlp) {
-					Ok(num) => Ok($name::from_bits(num)),
-					Err(err) => Err(err),
-				}
+				crate::nonstandard::Float::decode(rlp).map(|float| float.0)
 			}
 		}
 	};
 }
+#[cfg(feature = "non-standard")]
 impl_encodable_for_f!(f32, u32);
+#[cfg(feature = "non-standard")]
 impl_decodable_for_f!(f32, u32);
+#[cfg(feature = "non-standard")]
 impl_encodable_for_f!(f64, u64);
+#[cfg(feature = "non-standard")]
 impl_decodable_for_f!(f64, u64);
 
 
 
-#[macro_export]
-macro_rules! impl_array_rlp {
-	($size: expr) => {
-		impl Encodable for [u8;$size] {
-			fn rlp_append(&self, s: &mut RlpStream) {
-				s.encoder().encode_value(self.as_ref());
+impl<const N: usize> Encodable for [u8; N] {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.encoder().encode_value(self.as_ref());
+	}
+}
+
+impl<const N: usize> Decodable for [u8; N] {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		rlp.decoder().decode_value(|bytes| match bytes.len().cmp(&N) {
+			core::cmp::Ordering::Less => Err(DecoderError::RlpIsTooShort),
+			core::cmp::Ordering::Greater => Err(DecoderError::RlpIsTooBig),
+			core::cmp::Ordering::Equal => {
+				let mut t = [0u8; N];
+				t.copy_from_slice(bytes);
+				Ok(t)
 			}
+		})
+	}
+}
+
+/// Marker for element types that can appear inside the generic `Vec`/slice/
+/// array list impls below (`Vec<T>`, `&[T]`, `[T; N]`).
+///
+/// This is `pub` so any `Encodable` type, including ones defined outside
+/// this crate (an `H256`/`Address` newtype, say), can opt in with a plain
+/// `impl RlpElement for MyType {}` and immediately get `Vec<MyType>` etc. for
+/// free. It can't be a true blanket `impl<T: Encodable> RlpElement for T {}`
+/// on stable Rust: `u8` deliberately does not implement it, since `Vec<u8>`/
+/// `[u8; N]` already have dedicated impls above that encode as a single RLP
+/// byte string, and a blanket impl would make those overlap with the generic
+/// list impls below.
+pub trait RlpElement: Encodable {}
+
+impl RlpElement for bool {}
+impl RlpElement for u16 {}
+impl RlpElement for u32 {}
+impl RlpElement for u64 {}
+impl RlpElement for u128 {}
+impl RlpElement for usize {}
+#[cfg(feature = "non-standard")]
+impl RlpElement for i8 {}
+#[cfg(feature = "non-standard")]
+impl RlpElement for i16 {}
+#[cfg(feature = "non-standard")]
+impl RlpElement for i32 {}
+#[cfg(feature = "non-standard")]
+impl RlpElement for i64 {}
+#[cfg(feature = "non-standard")]
+impl RlpElement for i128 {}
+#[cfg(feature = "non-standard")]
+impl RlpElement for f32 {}
+#[cfg(feature = "non-standard")]
+impl RlpElement for f64 {}
+impl RlpElement for String {}
+impl<T: RlpElement> RlpElement for Option<T> {}
+impl<T: RlpElement + ?Sized> RlpElement for Box<T> {}
+
+impl<T: RlpElement> Encodable for Vec<T> {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(self.len());
+		for item in self {
+			s.append(item);
 		}
+	}
+}
 
-		impl Decodable for [u8;$size] {
-			fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-				rlp.decoder().decode_value(|bytes| match bytes.len().cmp(&$size) {
-					std::cmp::Ordering::Less => Err(DecoderError::RlpIsTooShort),
-					std::cmp::Ordering::Greater => Err(DecoderError::RlpIsTooBig),
-					std::cmp::Ordering::Equal => {
-						let mut t = [0u8; $size];
-						t.copy_from_slice(bytes);
-						Ok(t)
-					}
-				})
-			}
+impl<T: RlpElement + Decodable> Decodable for Vec<T> {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		let mut result = Vec::with_capacity(rlp.item_count()?);
+		for i in 0..rlp.item_count()? {
+			result.push(rlp.val_at(i)?);
 		}
-	};
+		Ok(result)
+	}
 }
 
-impl_array_rlp!(4);
-impl_array_rlp!(8);
-impl_array_rlp!(16);
-impl_array_rlp!(32);
-impl_array_rlp!(64);
-impl_array_rlp!(128);
\ No newline at end of file
+impl<'a, T: RlpElement> Encodable for &'a [T] {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(self.len());
+		for item in *self {
+			s.append(item);
+		}
+	}
+}
+
+impl<T: RlpElement, const N: usize> Encodable for [T; N] {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(N);
+		for item in self {
+			s.append(item);
+		}
+	}
+}
+
+impl<T: RlpElement + Decodable, const N: usize> Decodable for [T; N] {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		let items = rlp.item_count()?;
+		if items != N {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		let mut result = Vec::with_capacity(N);
+		for i in 0..N {
+			result.push(rlp.val_at(i)?);
+		}
+		result.try_into().map_err(|_| DecoderError::RlpIncorrectListLen)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{decode, encode};
+
+	#[test]
+	fn vec_of_arbitrary_encodable_round_trips() {
+		let value: Vec<u32> = vec![1, 256, 65536];
+		let encoded = encode(&value);
+		assert_eq!(decode::<Vec<u32>>(&encoded).unwrap(), value);
+	}
+
+	#[test]
+	fn array_of_arbitrary_encodable_round_trips() {
+		let value: [u32; 3] = [1, 2, 3];
+		let encoded = encode(&value);
+		assert_eq!(decode::<[u32; 3]>(&encoded).unwrap(), value);
+	}
+
+	#[test]
+	fn slice_of_arbitrary_encodable_encodes_as_list() {
+		let value: &[u32] = &[1, 2, 3];
+		let encoded = encode(&value);
+		assert_eq!(decode::<Vec<u32>>(&encoded).unwrap(), vec![1, 2, 3]);
+	}
+
+	#[test]
+	fn const_generic_byte_array_round_trips_odd_length() {
+		// `[u8; 20]` (an Ethereum address) had no impl under the old
+		// `impl_array_rlp!` macro, which only covered 4/8/16/32/64/128.
+		let value: [u8; 20] = [7; 20];
+		let encoded = encode(&value);
+		assert_eq!(decode::<[u8; 20]>(&encoded).unwrap(), value);
+	}
+
+	#[test]
+	fn const_generic_byte_array_decode_rejects_short_input() {
+		let encoded = encode(&[0u8; 10]);
+		assert_eq!(decode::<[u8; 20]>(&encoded), Err(DecoderError::RlpIsTooShort));
+	}
+
+	#[test]
+	fn const_generic_byte_array_decode_rejects_long_input() {
+		let encoded = encode(&[0u8; 40]);
+		assert_eq!(decode::<[u8; 20]>(&encoded), Err(DecoderError::RlpIsTooBig));
+	}
+}
\ No newline at end of file
@@ -0,0 +1,112 @@
+// Copyright 2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Explicit, typed wrappers for this crate's non-standard integer/float
+//! extensions.
+//!
+//! Canonical RLP only defines encoding for unsigned integers and byte
+//! strings; this crate additionally knows how to zig-zag encode signed
+//! integers and encode floats as raw IEEE-754 bits, neither of which is
+//! interoperable with other RLP decoders, and both of which are
+//! indistinguishable on the wire from an unsigned value of the same
+//! magnitude. Wrap a value in [`Signed`]/[`Float`] to opt into these
+//! encodings explicitly, rather than relying on the bare `iN`/`fN` impls
+//! (only available behind the `non-standard` feature) and risking a
+//! silently misinterpreted sign bit.
+
+use crate::error::DecoderError;
+use crate::rlpin::Rlp;
+use crate::stream::RlpStream;
+use crate::traits::{Decodable, Encodable};
+
+/// A signed integer, encoded with this crate's non-standard zig-zag scheme.
+///
+/// See the [module docs](self) for why this isn't just the bare `iN` impl.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Signed<T>(pub T);
+
+/// A float, encoded as its raw IEEE-754 bits — this crate's non-standard
+/// extension for floating point values.
+///
+/// See the [module docs](self) for why this isn't just the bare `fN` impl.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Float<T>(pub T);
+
+macro_rules! impl_signed {
+	($name: ident) => {
+		impl Encodable for Signed<$name> {
+			fn rlp_append(&self, s: &mut RlpStream) {
+				let i = self.0 as i128;
+				let zigzag = ((i << 1) ^ (i >> 127)) as u128;
+				let leading_empty_bytes = zigzag.leading_zeros() as usize / 8;
+				let buffer = zigzag.to_be_bytes();
+				s.encoder().encode_value(&buffer[leading_empty_bytes..]);
+			}
+		}
+
+		impl Decodable for Signed<$name> {
+			fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+				u128::decode(rlp).map(|res| {
+					let recovered = ((res >> 1) ^ (-((res & 1) as i128)) as u128) as $name;
+					Signed(recovered)
+				})
+			}
+		}
+	};
+}
+
+impl_signed!(i8);
+impl_signed!(i16);
+impl_signed!(i32);
+impl_signed!(i64);
+impl_signed!(i128);
+
+macro_rules! impl_float {
+	($name: ident, $value: ident) => {
+		impl Encodable for Float<$name> {
+			fn rlp_append(&self, s: &mut RlpStream) {
+				let num = $value::from_be_bytes(self.0.to_bits().to_be_bytes());
+				num.rlp_append(s);
+			}
+		}
+
+		impl Decodable for Float<$name> {
+			fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+				$value::decode(rlp).map(|num| Float($name::from_bits(num)))
+			}
+		}
+	};
+}
+
+impl_float!(f32, u32);
+impl_float!(f64, u64);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{decode, encode};
+
+	#[test]
+	fn signed_and_unsigned_same_magnitude_are_distinguishable() {
+		// `-1i64` zig-zags to the same wire bytes as `1u64`; decoding those
+		// bytes back must not silently reproduce `-1` unless the type at the
+		// decode site says so.
+		let encoded_unsigned = encode(&1u64);
+		let encoded_signed_neg_one = encode(&Signed(-1i64));
+		assert_eq!(encoded_unsigned, encoded_signed_neg_one);
+
+		assert_eq!(decode::<u64>(&encoded_unsigned).unwrap(), 1u64);
+		assert_eq!(decode::<Signed<i64>>(&encoded_signed_neg_one).unwrap(), Signed(-1i64));
+	}
+
+	#[test]
+	fn float_round_trips() {
+		let encoded = encode(&Float(1.5f64));
+		assert_eq!(decode::<Float<f64>>(&encoded).unwrap().0, 1.5f64);
+	}
+}
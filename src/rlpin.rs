@@ -0,0 +1,260 @@
+// Copyright 2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::fmt;
+
+use crate::error::DecoderError;
+use crate::traits::Decodable;
+
+/// The most recently resolved `(item_index, byte_offset)` pair for a list.
+#[derive(Debug, Clone, Copy)]
+struct OffsetCache {
+	index: usize,
+	offset: usize,
+}
+
+impl OffsetCache {
+	fn new(index: usize, offset: usize) -> OffsetCache {
+		OffsetCache { index, offset }
+	}
+}
+
+/// Description of the payload of an RLP item: the length in bytes of its
+/// header, and the length in bytes of the value that follows it.
+#[derive(Debug, Copy, Clone)]
+pub struct PayloadInfo {
+	pub header_len: usize,
+	pub value_len: usize,
+}
+
+impl PayloadInfo {
+	fn new(header_len: usize, value_len: usize) -> PayloadInfo {
+		PayloadInfo { header_len, value_len }
+	}
+
+	/// Total length of the header plus the value.
+	pub fn total(&self) -> usize {
+		self.header_len + self.value_len
+	}
+}
+
+/// An immutable, read-only view onto an RLP-encoded byte slice.
+///
+/// Indexing into a list (`at`/`val_at`) walks the payload item by item, since
+/// RLP carries no explicit offset table. `Rlp` keeps a one-entry cache of the
+/// most recently resolved `(item_index, byte_offset)` pair so that decoding a
+/// struct's fields in order -- the overwhelmingly common access pattern --
+/// only ever has to skip forward from where the previous lookup left off,
+/// rather than re-walking from the start of the list each time.
+///
+/// That cache is an interior-mutable `Cell`, which is an intentional
+/// tradeoff: unlike a bare `&'a [u8]`, `Rlp` is no longer `Sync`, so it can't
+/// be shared by reference across threads (e.g. decoding sibling fields in
+/// parallel via scoped threads/rayon). Clone it (cheap: it just re-wraps the
+/// same `&'a [u8]` with a fresh, unresolved cache) to hand a copy to another
+/// thread instead.
+pub struct Rlp<'a> {
+	bytes: &'a [u8],
+	offset_cache: Cell<OffsetCache>,
+	count_cache: Cell<Option<usize>>,
+}
+
+impl<'a> fmt::Debug for Rlp<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Rlp").field("bytes", &self.bytes).finish()
+	}
+}
+
+impl<'a> Clone for Rlp<'a> {
+	fn clone(&self) -> Self {
+		Rlp::new(self.bytes)
+	}
+}
+
+impl<'a> Rlp<'a> {
+	/// Create a new instance of `Rlp` reading from `bytes`.
+	pub fn new(bytes: &'a [u8]) -> Rlp<'a> {
+		// `index: usize::MAX` is a sentinel meaning "nothing resolved yet", so
+		// the first lookup always falls back to scanning from the header.
+		Rlp { bytes, offset_cache: Cell::new(OffsetCache::new(usize::MAX, 0)), count_cache: Cell::new(None) }
+	}
+
+	/// The raw bytes this `Rlp` was constructed from.
+	pub fn as_raw(&self) -> &'a [u8] {
+		self.bytes
+	}
+
+	fn payload_info(&self) -> Result<PayloadInfo, DecoderError> {
+		Self::payload_info_at(self.bytes, 0).map(|(info, _)| info)
+	}
+
+	/// Parse the header of the item starting at `offset`, returning its
+	/// `PayloadInfo` and the offset of the first byte after the header.
+	fn payload_info_at(bytes: &[u8], offset: usize) -> Result<(PayloadInfo, usize), DecoderError> {
+		let prefix = *bytes.get(offset).ok_or(DecoderError::RlpIsTooShort)?;
+		match prefix {
+			0x00..=0x7f => Ok((PayloadInfo::new(0, 1), offset)),
+			0x80..=0xb7 => Ok((PayloadInfo::new(1, (prefix - 0x80) as usize), offset + 1)),
+			0xb8..=0xbf => {
+				let len_of_len = (prefix - 0xb7) as usize;
+				let value_len = Self::read_length(bytes, offset + 1, len_of_len)?;
+				Ok((PayloadInfo::new(1 + len_of_len, value_len), offset + 1 + len_of_len))
+			}
+			0xc0..=0xf7 => Ok((PayloadInfo::new(1, (prefix - 0xc0) as usize), offset + 1)),
+			0xf8..=0xff => {
+				let len_of_len = (prefix - 0xf7) as usize;
+				let value_len = Self::read_length(bytes, offset + 1, len_of_len)?;
+				Ok((PayloadInfo::new(1 + len_of_len, value_len), offset + 1 + len_of_len))
+			}
+		}
+	}
+
+	fn read_length(bytes: &[u8], offset: usize, len: usize) -> Result<usize, DecoderError> {
+		let slice = bytes.get(offset..offset + len).ok_or(DecoderError::RlpIsTooShort)?;
+		if slice.first() == Some(&0) {
+			return Err(DecoderError::RlpInvalidIndirection);
+		}
+		let mut value = 0usize;
+		for byte in slice {
+			value = value.checked_shl(8).ok_or(DecoderError::RlpIsTooBig)?;
+			value += *byte as usize;
+		}
+		Ok(value)
+	}
+
+	/// Number of items in this RLP list.
+	pub fn item_count(&self) -> Result<usize, DecoderError> {
+		if let Some(count) = self.count_cache.get() {
+			return Ok(count);
+		}
+		let mut count = 0;
+		let mut offset = self.payload_info()?.header_len;
+		let total = self.bytes.len();
+		while offset < total {
+			offset += self.item_len_at(offset)?;
+			count += 1;
+		}
+		self.count_cache.set(Some(count));
+		Ok(count)
+	}
+
+	/// Total byte length (header + value) of the item starting at `offset`.
+	fn item_len_at(&self, offset: usize) -> Result<usize, DecoderError> {
+		let (info, _) = Self::payload_info_at(self.bytes, offset)?;
+		Ok(info.total())
+	}
+
+	/// Resolve the byte offset of item `index`, scanning forward from the
+	/// cached `(index, offset)` pair when possible instead of from the start
+	/// of the list.
+	fn offset_of(&self, index: usize) -> Result<usize, DecoderError> {
+		let header_len = self.payload_info()?.header_len;
+		let cached = self.offset_cache.get();
+		let (mut seen, mut offset) =
+			if cached.index <= index { (cached.index, cached.offset) } else { (0, header_len) };
+
+		while seen < index {
+			offset += self.item_len_at(offset)?;
+			seen += 1;
+		}
+
+		self.offset_cache.set(OffsetCache::new(index, offset));
+		Ok(offset)
+	}
+
+	/// Return the sub-item at `index` as its own `Rlp`.
+	pub fn at(&self, index: usize) -> Result<Rlp<'a>, DecoderError> {
+		let offset = self.offset_of(index)?;
+		let len = self.item_len_at(offset)?;
+		let end = offset.checked_add(len).ok_or(DecoderError::RlpIsTooShort)?;
+		let slice = self.bytes.get(offset..end).ok_or(DecoderError::RlpIsTooShort)?;
+		Ok(Rlp::new(slice))
+	}
+
+	/// Decode the item at `index` as `T`.
+	pub fn val_at<T: Decodable>(&self, index: usize) -> Result<T, DecoderError> {
+		T::decode(&self.at(index)?)
+	}
+
+	/// Access the low-level byte-string decoder for this item. Used by the
+	/// scalar `Decodable` impls in `impls.rs` (`u8`, `bool`, the `u*`/`i*`
+	/// macros, `String`, ...).
+	pub fn decoder(&self) -> BasicDecoder<'a, '_> {
+		BasicDecoder { rlp: self }
+	}
+
+	/// This item's payload, with the RLP string header stripped off. Returns
+	/// `RlpExpectedToBeData` if this item is a list rather than a string.
+	fn as_value(&self) -> Result<&'a [u8], DecoderError> {
+		let prefix = *self.bytes.first().ok_or(DecoderError::RlpIsTooShort)?;
+		match prefix {
+			0x00..=0x7f => Ok(&self.bytes[..1]),
+			0x80..=0xb7 => Ok(&self.bytes[1..1 + (prefix - 0x80) as usize]),
+			0xb8..=0xbf => {
+				let len_of_len = (prefix - 0xb7) as usize;
+				let value_len = Self::read_length(self.bytes, 1, len_of_len)?;
+				let start = 1 + len_of_len;
+				self.bytes.get(start..start + value_len).ok_or(DecoderError::RlpIsTooShort)
+			}
+			0xc0..=0xff => Err(DecoderError::RlpExpectedToBeData),
+		}
+	}
+
+	/// Reverse a [`crate::compression::compress`]ed payload back into the raw
+	/// RLP bytes this `Rlp` would otherwise have been constructed from. The
+	/// compressed payload is itself a normal RLP byte string (written via
+	/// [`crate::stream::RlpStream::append_compressed`]), so this strips that
+	/// header before undoing the fragment substitution.
+	pub fn decompress(&self) -> Result<Vec<u8>, DecoderError> {
+		crate::compression::decompress(self.as_value()?)
+	}
+}
+
+/// Low-level helper returned by [`Rlp::decoder`] for reading a single RLP
+/// string value (the scalar types all bottom out here).
+pub struct BasicDecoder<'a, 'b> {
+	rlp: &'b Rlp<'a>,
+}
+
+impl<'a, 'b> BasicDecoder<'a, 'b> {
+	/// Run `f` over this item's payload bytes (header stripped).
+	pub fn decode_value<T>(self, f: impl FnOnce(&'a [u8]) -> Result<T, DecoderError>) -> Result<T, DecoderError> {
+		f(self.rlp.as_value()?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sequential_val_at_uses_cache_not_full_rescan() {
+		// [1, 2, 3, 4, 5] rlp-encoded as a list of single-byte values.
+		let rlp = Rlp::new(&[0xc5, 1, 2, 3, 4, 5]);
+		assert_eq!(rlp.item_count().unwrap(), 5);
+		for i in 0..5u8 {
+			let item = rlp.at(i as usize).unwrap();
+			assert_eq!(item.as_raw(), &[i + 1]);
+			// The cache now holds this item's position, so the next lookup
+			// starts from here instead of from the list header.
+			assert_eq!(rlp.offset_cache.get().index, i as usize);
+			assert_eq!(rlp.offset_cache.get().offset, 1 + i as usize);
+		}
+	}
+
+	#[test]
+	fn out_of_order_access_still_resolves_correctly() {
+		let rlp = Rlp::new(&[0xc5, 1, 2, 3, 4, 5]);
+		assert_eq!(rlp.at(4).unwrap().as_raw(), &[5]);
+		assert_eq!(rlp.at(1).unwrap().as_raw(), &[2]);
+		assert_eq!(rlp.at(3).unwrap().as_raw(), &[4]);
+	}
+}
@@ -0,0 +1,173 @@
+// Copyright 2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::traits::Encodable;
+
+fn length_bytes(mut value: usize) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	while value > 0 {
+		bytes.push((value & 0xff) as u8);
+		value >>= 8;
+	}
+	bytes.reverse();
+	bytes
+}
+
+fn append_string_header(out: &mut Vec<u8>, bytes: &[u8]) {
+	if bytes.len() == 1 && bytes[0] < 0x80 {
+		// single literal byte: no header at all
+	} else if bytes.len() < 56 {
+		out.push(0x80 + bytes.len() as u8);
+	} else {
+		let len = length_bytes(bytes.len());
+		out.push(0xb7 + len.len() as u8);
+		out.extend_from_slice(&len);
+	}
+}
+
+fn list_header(content_len: usize) -> Vec<u8> {
+	let mut out = Vec::new();
+	if content_len < 56 {
+		out.push(0xc0 + content_len as u8);
+	} else {
+		let len = length_bytes(content_len);
+		out.push(0xf7 + len.len() as u8);
+		out.extend_from_slice(&len);
+	}
+	out
+}
+
+/// One level of `RlpStream`'s open-list stack: the bytes appended so far at
+/// this level, and how many more direct children it still expects before its
+/// list header can be written and the level folded into its parent.
+struct Level {
+	buffer: Vec<u8>,
+	remaining: usize,
+}
+
+/// `usize::MAX` marks the implicit root level, which is never auto-closed:
+/// a stream need not be wrapped in an outer list (e.g. encoding a single
+/// scalar via `append`).
+const ROOT: usize = usize::MAX;
+
+/// A growable buffer that items are appended to in order to build up an
+/// RLP-encoded byte string or list.
+pub struct RlpStream {
+	levels: Vec<Level>,
+}
+
+impl Default for RlpStream {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl RlpStream {
+	/// Start a new, empty stream.
+	pub fn new() -> Self {
+		let mut levels = Vec::with_capacity(1);
+		levels.push(Level { buffer: Vec::new(), remaining: ROOT });
+		RlpStream { levels }
+	}
+
+	/// Open a list of `len` items; the next `len` calls to `append` (or
+	/// `begin_list`, for a nested list) are collected as this list's
+	/// children, and its header is written once the last one completes.
+	pub fn begin_list(&mut self, len: usize) -> &mut Self {
+		self.levels.push(Level { buffer: Vec::new(), remaining: len });
+		if len == 0 {
+			self.close_top_level();
+		}
+		self
+	}
+
+	/// Append a single `Encodable` value as the next item at the current
+	/// list level (or as the whole stream, at the root level).
+	pub fn append<E: Encodable + ?Sized>(&mut self, value: &E) -> &mut Self {
+		value.rlp_append(self);
+		self
+	}
+
+	/// Append `value`'s RLP encoding after running it through
+	/// [`crate::compression::compress`], so recurring fragments (an
+	/// all-zero hash, ...) collapse to a two-byte escape sequence. The
+	/// compressed bytes are themselves wrapped in an RLP string header, so
+	/// this is safe to use anywhere `append` is -- a sibling item, say --
+	/// and not just at the stream root. Pair with
+	/// [`crate::rlpin::Rlp::decompress`] on the decode side.
+	pub fn append_compressed<E: Encodable>(&mut self, value: &E) -> &mut Self {
+		let mut scratch = RlpStream::new();
+		scratch.append(value);
+		let compressed = crate::compression::compress(&scratch.out());
+		self.encoder().encode_value(&compressed);
+		self
+	}
+
+	/// Access the low-level byte/value encoder for this stream. Used by the
+	/// scalar `Encodable` impls (`u8`, `bool`, the `u*`/`i*` macros, ...).
+	pub fn encoder(&mut self) -> BasicEncoder<'_> {
+		BasicEncoder { stream: self }
+	}
+
+	fn write_raw(&mut self, bytes: &[u8]) {
+		let top = self.levels.last_mut().expect("RlpStream always has at least the root level");
+		top.buffer.extend_from_slice(bytes);
+		if top.remaining != ROOT {
+			top.remaining -= 1;
+		}
+		if top.remaining == 0 {
+			self.close_top_level();
+		}
+	}
+
+	fn close_top_level(&mut self) {
+		let level = self.levels.pop().expect("begin_list always pushes before this is called");
+		let mut item = list_header(level.buffer.len());
+		item.extend_from_slice(&level.buffer);
+		self.write_raw(&item);
+	}
+
+	/// The bytes written so far at the root level. Panics if there is an
+	/// open `begin_list` that hasn't received all of its children yet.
+	pub fn as_raw(&self) -> &[u8] {
+		assert_eq!(self.levels.len(), 1, "RlpStream has an unfinished list");
+		&self.levels[0].buffer
+	}
+
+	/// Consume the stream, returning the completed bytes. Panics if there is
+	/// an open `begin_list` that hasn't received all of its children yet.
+	pub fn out(self) -> Vec<u8> {
+		assert_eq!(self.levels.len(), 1, "RlpStream has an unfinished list");
+		self.levels.into_iter().next().unwrap().buffer
+	}
+}
+
+/// Low-level helper returned by [`RlpStream::encoder`] for writing a single
+/// RLP string value (the scalar types all bottom out here).
+pub struct BasicEncoder<'a> {
+	stream: &'a mut RlpStream,
+}
+
+impl<'a> BasicEncoder<'a> {
+	/// Write `bytes` as a single RLP byte string.
+	pub fn encode_value(self, bytes: &[u8]) {
+		let mut out = Vec::with_capacity(bytes.len() + 9);
+		append_string_header(&mut out, bytes);
+		out.extend_from_slice(bytes);
+		self.stream.write_raw(&out);
+	}
+
+	/// Write the bytes yielded by `iter` as a single RLP byte string.
+	pub fn encode_iter<I: Iterator<Item = u8>>(self, iter: I) {
+		let bytes: Vec<u8> = iter.collect();
+		self.encode_value(&bytes);
+	}
+}